@@ -3,14 +3,15 @@ use crate::{
     config::Config,
     error::{Error, Result},
 };
-use derive_more::Deref;
 use enum_dispatch::enum_dispatch;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
+    collections::HashMap,
     convert::TryFrom,
     ffi::OsString,
     fmt::Display,
     hash::{Hash, Hasher},
+    ops::Deref,
     path::PathBuf,
     str::FromStr,
 };
@@ -104,12 +105,86 @@ impl DesktopHandler {
 }
 
 /// Represents a regex handler from the config
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RegexHandler {
     exec: String,
-    #[serde(default)]
     terminal: bool,
     regexes: RegexSet,
+    /// URL schemes (e.g. `mailto`, `magnet`) this handler matches directly,
+    /// without needing a regex pattern
+    schemes: Vec<String>,
+}
+
+/// Flags controlling how a `RegexHandler`'s patterns are compiled, mirroring
+/// the toggles exposed by a typical regex testing tool
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize,
+)]
+#[serde(default)]
+struct RegexFlags {
+    case_insensitive: bool,
+    anchored: bool,
+    multi_line: bool,
+    dot_matches_new_line: bool,
+}
+
+/// Raw, on-disk representation of a [`RegexHandler`], before its patterns are
+/// compiled with `regexes`' flags applied
+#[derive(Deserialize)]
+struct RegexHandlerRaw {
+    exec: String,
+    #[serde(default)]
+    terminal: bool,
+    #[serde(default)]
+    regexes: Vec<String>,
+    #[serde(default)]
+    schemes: Vec<String>,
+    #[serde(flatten)]
+    flags: RegexFlags,
+}
+
+impl<'de> Deserialize<'de> for RegexHandler {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = RegexHandlerRaw::deserialize(deserializer)?;
+        let regexes = RegexSet::new_with_flags(&raw.regexes, raw.flags)
+            .map_err(serde::de::Error::custom)?;
+
+        Ok(RegexHandler {
+            exec: raw.exec,
+            terminal: raw.terminal,
+            regexes,
+            schemes: raw.schemes,
+        })
+    }
+}
+
+impl Serialize for RegexHandler {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct RegexHandlerSer<'a> {
+            exec: &'a str,
+            terminal: bool,
+            regexes: &'a RegexSet,
+            schemes: &'a [String],
+            #[serde(flatten)]
+            flags: RegexFlags,
+        }
+
+        RegexHandlerSer {
+            exec: &self.exec,
+            terminal: self.terminal,
+            regexes: &self.regexes,
+            schemes: &self.schemes,
+            flags: self.regexes.flags,
+        }
+        .serialize(serializer)
+    }
 }
 
 impl RegexHandler {
@@ -117,6 +192,136 @@ impl RegexHandler {
     fn is_match(&self, path: &str) -> bool {
         self.regexes.is_match(path)
     }
+
+    /// Test if a given path is a URL whose scheme is one of this handler's
+    /// configured `schemes`
+    fn matches_scheme(&self, path: &UserPath) -> bool {
+        match path {
+            UserPath::Url(url) => {
+                self.schemes.iter().any(|scheme| scheme == url.scheme())
+            }
+            _ => false,
+        }
+    }
+
+    /// Find the first regex in the set that matches `path` and return its
+    /// captures, keyed by both group name and group index (as a string)
+    fn captures(&self, path: &str) -> Option<HashMap<String, String>> {
+        self.regexes.regexes.iter().find_map(|re| {
+            let caps = re.captures(path)?;
+
+            let mut groups = HashMap::new();
+            for (i, name) in re.capture_names().enumerate() {
+                let m = match caps.get(i) {
+                    Some(m) => m.as_str().to_string(),
+                    None => continue,
+                };
+                if let Some(name) = name {
+                    groups.insert(name.to_string(), m.clone());
+                }
+                groups.insert(i.to_string(), m);
+            }
+
+            Some(groups)
+        })
+    }
+
+    /// Get the desktop entry for this handler, substituting any `$name`,
+    /// `${name}`, or `$1`-style references in `exec` with the given capture
+    /// groups. Field codes like `%u`/`%f` are left untouched.
+    ///
+    /// Takes a *raw* handler whose `exec` hasn't already been resolved
+    /// (e.g. one returned by `matches_for`) — `get_handler` pre-resolves
+    /// `exec` itself, so callers of `get_handler` should use plain
+    /// `get_entry()` instead to avoid expanding the captures twice.
+    pub fn get_entry_for(
+        &self,
+        captures: &HashMap<String, String>,
+    ) -> Result<DesktopEntry> {
+        Ok(DesktopEntry::fake_entry(
+            &expand_captures(&self.exec, captures),
+            self.terminal,
+        ))
+    }
+
+    /// The `exec` that would run for `path`, after capture substitution.
+    /// Used to report what a handler would do without actually launching it
+    /// (e.g. in `handlr query`)
+    pub fn exec_for(&self, path: &UserPath) -> String {
+        // `get_handler` runs a scheme-matched handler's `exec` unexpanded
+        if self.matches_scheme(path) {
+            return self.exec.clone();
+        }
+        let captures = self.captures(&path.to_string()).unwrap_or_default();
+        expand_captures(&self.exec, &captures)
+    }
+
+    /// Substitute `captures` into `self.exec` in place, so that the regular
+    /// `Handleable::get_entry`/`open` path (which has no path to capture
+    /// against) launches the already-resolved command
+    fn apply_captures(mut self, captures: &HashMap<String, String>) -> Self {
+        self.exec = expand_captures(&self.exec, captures);
+        self
+    }
+}
+
+/// Expand `$name`, `${name}`, and `$1`-style references in `exec` using
+/// `captures`. A reference with no corresponding capture expands to the
+/// empty string, and a literal `$` can be escaped as `$$`.
+fn expand_captures(exec: &str, captures: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(exec.len());
+    let mut chars = exec.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.peek() {
+            Some('$') => {
+                chars.next();
+                out.push('$');
+            }
+            Some('{') => {
+                chars.next();
+                let name: String =
+                    chars.by_ref().take_while(|&c| c != '}').collect();
+                if let Some(val) = captures.get(&name) {
+                    push_escaped_capture(&mut out, val);
+                }
+            }
+            Some(c) if c.is_ascii_alphanumeric() || *c == '_' => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || c == '_' {
+                        name.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if let Some(val) = captures.get(&name) {
+                    push_escaped_capture(&mut out, val);
+                }
+            }
+            _ => out.push('$'),
+        }
+    }
+
+    out
+}
+
+/// Append a captured value to `exec`, escaping `%` as `%%` so a captured
+/// segment (e.g. from an attacker-controlled URL) can't be misread as an
+/// XDG `Exec=` field code (`%u`, `%f`, ...) once it reaches that machinery
+fn push_escaped_capture(out: &mut String, val: &str) {
+    for c in val.chars() {
+        if c == '%' {
+            out.push('%');
+        }
+        out.push(c);
+    }
 }
 
 impl Handleable for RegexHandler {
@@ -125,9 +330,92 @@ impl Handleable for RegexHandler {
     }
 }
 
-/// Helper struct needed because regex::RegexSet does not implement Hash
-#[derive(Deref, Debug, Clone, Deserialize)]
-struct RegexSet(#[serde(with = "serde_regex")] regex::RegexSet);
+/// Helper struct needed because regex::RegexSet does not implement Hash, and
+/// to keep the individual compiled `Regex`es around for capturing groups
+/// (`regex::RegexSet` itself cannot produce captures)
+#[derive(Debug, Clone)]
+struct RegexSet {
+    set: regex::RegexSet,
+    regexes: Vec<regex::Regex>,
+    flags: RegexFlags,
+    /// The original, unwrapped patterns as provided by the config, kept
+    /// separately from `set`/`regexes` (which may be `anchored`-wrapped) so
+    /// serializing and re-deserializing doesn't re-wrap an already-anchored
+    /// pattern
+    patterns: Vec<String>,
+}
+
+impl Deref for RegexSet {
+    type Target = regex::RegexSet;
+
+    fn deref(&self) -> &Self::Target {
+        &self.set
+    }
+}
+
+impl Serialize for RegexSet {
+    /// Emit the set's original patterns as a string array, mirroring the
+    /// format the custom `Deserialize` impl reads
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.patterns.serialize(serializer)
+    }
+}
+
+impl RegexSet {
+    /// Build a RegexSet from raw patterns, applying `flags` to every pattern
+    /// via `regex::RegexBuilder` before compiling it. The unwrapped
+    /// `patterns` are kept around for serialization; `anchored` is applied
+    /// only to the compiled forms, so it never compounds across round-trips
+    fn new_with_flags<S: AsRef<str>>(
+        patterns: &[S],
+        flags: RegexFlags,
+    ) -> Result<Self> {
+        let patterns: Vec<String> =
+            patterns.iter().map(|p| p.as_ref().to_string()).collect();
+
+        // `\A`/`\z` anchor to the whole input; unlike `^`/`$`, they aren't
+        // affected by `multi_line` and don't match before a trailing `\n`
+        let compiled: Vec<String> = patterns
+            .iter()
+            .map(|p| {
+                if flags.anchored {
+                    format!(r"\A(?:{p})\z")
+                } else {
+                    p.clone()
+                }
+            })
+            .collect();
+
+        let build = |p: &str| -> Result<regex::Regex> {
+            Ok(regex::RegexBuilder::new(p)
+                .case_insensitive(flags.case_insensitive)
+                .multi_line(flags.multi_line)
+                .dot_matches_new_line(flags.dot_matches_new_line)
+                .build()?)
+        };
+
+        let regexes = compiled
+            .iter()
+            .map(|p| build(p))
+            .collect::<Result<Vec<_>>>()?;
+
+        let set = regex::RegexSetBuilder::new(&compiled)
+            .case_insensitive(flags.case_insensitive)
+            .multi_line(flags.multi_line)
+            .dot_matches_new_line(flags.dot_matches_new_line)
+            .build()?;
+
+        Ok(RegexSet {
+            set,
+            regexes,
+            flags,
+            patterns,
+        })
+    }
+}
 
 #[cfg(test)]
 impl RegexSet {
@@ -137,14 +425,16 @@ impl RegexSet {
         S: AsRef<str>,
         I: IntoIterator<Item = S>,
     {
-        Ok(RegexSet(regex::RegexSet::new(exprs)?))
+        let exprs: Vec<String> =
+            exprs.into_iter().map(|s| s.as_ref().to_string()).collect();
+        RegexSet::new_with_flags(&exprs, RegexFlags::default())
     }
 }
 
 impl PartialEq for RegexSet {
     #[mutants::skip] // Trivial
     fn eq(&self, other: &Self) -> bool {
-        self.patterns() == other.patterns()
+        self.patterns == other.patterns && self.flags == other.flags
     }
 }
 
@@ -153,23 +443,100 @@ impl Eq for RegexSet {}
 impl Hash for RegexSet {
     #[mutants::skip] // Trivial
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.patterns().hash(state);
+        self.patterns.hash(state);
+        self.flags.hash(state);
     }
 }
 
 /// A collection of all of the defined RegexHandlers
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct RegexApps(Vec<RegexHandler>);
 
+/// Common non-http(s) URL schemes worth dispatching purely by scheme, paired
+/// with a sensible default `exec`, so users get working mailto/magnet/etc
+/// dispatch without having to write a handler themselves
+const DEFAULT_SCHEME_HANDLERS: &[(&str, &str)] = &[
+    ("mailto", "xdg-email %u"),
+    ("magnet", "xdg-open %u"),
+    ("gemini", "xdg-open %u"),
+    ("ipfs", "xdg-open %u"),
+    ("git", "xdg-open %u"),
+    ("ssh", "xdg-open %u"),
+];
+
+/// This is the value used when the config has no regex apps configured at
+/// all (e.g. via `#[serde(default)]`), so it seeds a sensible starting point
+/// rather than leaving scheme-based dispatch empty. It is meant as a
+/// fallback for users who haven't configured regex handlers yet, not a
+/// merge applied on top of a user's existing config — once a user adds any
+/// entry to their `[[regex_apps]]`, these defaults are no longer consulted,
+/// so a user relying on a different `mailto`/`magnet`/etc. handler should
+/// add their own entry rather than expecting these to be overridden in
+/// place.
+impl Default for RegexApps {
+    fn default() -> Self {
+        RegexApps(
+            DEFAULT_SCHEME_HANDLERS
+                .iter()
+                .map(|(scheme, exec)| RegexHandler {
+                    exec: exec.to_string(),
+                    terminal: false,
+                    regexes: RegexSet::new_with_flags::<String>(
+                        &[],
+                        RegexFlags::default(),
+                    )
+                    .expect("an empty pattern list always compiles"),
+                    schemes: vec![scheme.to_string()],
+                })
+                .collect(),
+        )
+    }
+}
+
 impl RegexApps {
-    /// Get a handler matching a given path
-    pub fn get_handler(&self, path: &UserPath) -> Result<RegexHandler> {
-        Ok(self
+    /// Get a handler matching a given path, along with the capture groups
+    /// (keyed by both group name and group index) produced by whichever of
+    /// its regexes matched. Explicit scheme handlers take priority over
+    /// regex handlers (in config order within each group), matching
+    /// `matches_for`'s ordering so shadowing reported there reflects what
+    /// actually runs
+    pub fn get_handler(
+        &self,
+        path: &UserPath,
+    ) -> Result<(RegexHandler, HashMap<String, String>)> {
+        if let Some(app) = self.0.iter().find(|app| app.matches_scheme(path))
+        {
+            return Ok((app.clone(), HashMap::new()));
+        }
+
+        let path_str = path.to_string();
+
+        let app = self
             .0
             .iter()
-            .find(|app| app.is_match(&path.to_string()))
-            .ok_or_else(|| Error::NotFound(path.to_string()))?
-            .clone())
+            .find(|app| app.is_match(&path_str))
+            .ok_or_else(|| Error::NotFound(path_str.clone()))?;
+
+        let captures = app.captures(&path_str).unwrap_or_default();
+        Ok((app.clone().apply_captures(&captures), captures))
+    }
+
+    /// Get every handler that matches a given path, with scheme matches
+    /// first (in config order) followed by regex matches (in config order),
+    /// mirroring `get_handler`'s precedence. Used to debug shadowing between
+    /// handlers (e.g. by `handlr query`)
+    pub fn matches_for(&self, path: &UserPath) -> Vec<&RegexHandler> {
+        let path_str = path.to_string();
+
+        let scheme_matches =
+            self.0.iter().filter(|app| app.matches_scheme(path));
+        let regex_matches = self
+            .0
+            .iter()
+            .filter(|app| !app.matches_scheme(path))
+            .filter(|app| app.is_match(&path_str));
+
+        scheme_matches.chain(regex_matches).collect()
     }
 }
 
@@ -189,16 +556,17 @@ mod tests {
             exec: String::from(exec),
             terminal: false,
             regexes: RegexSet::new(regexes)?,
+            schemes: Vec::new(),
         };
 
         let regex_apps = RegexApps(vec![regex_handler.clone()]);
 
+        let (handler, _captures) = regex_apps.get_handler(&UserPath::Url(
+            Url::parse("https://youtu.be/dQw4w9WgXcQ")?,
+        ))?;
+
         assert_eq!(
-            regex_apps
-                .get_handler(&UserPath::Url(Url::parse(
-                    "https://youtu.be/dQw4w9WgXcQ"
-                )?))?
-                .get_entry()?,
+            handler.get_entry()?,
             DesktopEntry {
                 exec: exec.to_string(),
                 terminal: false,
@@ -214,4 +582,329 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn regex_handler_capture_substitution() -> Result<()> {
+        let regex_handler = RegexHandler {
+            exec: String::from(
+                "git-clone-tui https://github.com/$owner/$repo",
+            ),
+            terminal: false,
+            regexes: RegexSet::new(&[
+                r"https://github\.com/(?P<owner>[^/]+)/(?P<repo>[^/]+)",
+            ])?,
+            schemes: Vec::new(),
+        };
+
+        let regex_apps = RegexApps(vec![regex_handler]);
+
+        let (handler, _captures) = regex_apps.get_handler(&UserPath::Url(
+            Url::parse("https://github.com/sirn-se/handlr-regex")?,
+        ))?;
+
+        assert_eq!(
+            handler.get_entry()?,
+            DesktopEntry {
+                exec: String::from(
+                    "git-clone-tui https://github.com/sirn-se/handlr-regex"
+                ),
+                terminal: false,
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_handler_only_expands_captures_once() -> Result<()> {
+        // A captured value that itself contains a literal `$` must survive
+        // untouched: `get_handler` resolves `exec` exactly once, so a second
+        // expansion (e.g. via `get_entry_for`) must not run on the result.
+        let regex_handler = RegexHandler {
+            exec: String::from("open $path"),
+            terminal: false,
+            regexes: RegexSet::new(&[r"http://x/(?P<path>.*)"])?,
+            schemes: Vec::new(),
+        };
+
+        let regex_apps = RegexApps(vec![regex_handler]);
+
+        let (handler, _captures) = regex_apps
+            .get_handler(&UserPath::Url(Url::parse("http://x/a$2b")?))?;
+
+        assert_eq!(
+            handler.get_entry()?,
+            DesktopEntry {
+                exec: String::from("open a$2b"),
+                terminal: false,
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn regex_handler_case_insensitive_flag() -> Result<()> {
+        let regex_handler = RegexHandler {
+            exec: String::from("freetube %u"),
+            terminal: false,
+            regexes: RegexSet::new_with_flags(
+                &[r"^HTTPS://YOUTU\.BE/"],
+                RegexFlags {
+                    case_insensitive: true,
+                    ..Default::default()
+                },
+            )?,
+            schemes: Vec::new(),
+        };
+
+        let regex_apps = RegexApps(vec![regex_handler]);
+
+        assert!(regex_apps
+            .get_handler(&UserPath::Url(Url::parse(
+                "https://youtu.be/dQw4w9WgXcQ"
+            )?))
+            .is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn regex_handler_matches_by_scheme() -> Result<()> {
+        let regex_handler = RegexHandler {
+            exec: String::from("xdg-email %u"),
+            terminal: false,
+            regexes: RegexSet::new(Vec::<String>::new())?,
+            schemes: vec![String::from("mailto")],
+        };
+
+        let regex_apps = RegexApps(vec![regex_handler]);
+
+        let (handler, captures) = regex_apps.get_handler(&UserPath::Url(
+            Url::parse("mailto:user@example.com")?,
+        ))?;
+
+        assert!(captures.is_empty());
+        assert_eq!(
+            handler.get_entry()?,
+            DesktopEntry {
+                exec: String::from("xdg-email %u"),
+                terminal: false,
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn default_regex_apps_seeds_common_schemes() -> Result<()> {
+        let regex_apps = RegexApps::default();
+
+        assert!(regex_apps
+            .get_handler(&UserPath::Url(Url::parse(
+                "magnet:?xt=urn:btih:abc123"
+            )?))
+            .is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn matches_for_reports_every_shadowing_handler_in_order() -> Result<()> {
+        let catch_all = RegexHandler {
+            exec: String::from("xdg-open %u"),
+            terminal: false,
+            regexes: RegexSet::new(&[r"https://github\.com/.*"])?,
+            schemes: Vec::new(),
+        };
+        let specific = RegexHandler {
+            exec: String::from(
+                "git-clone-tui https://github.com/$owner/$repo",
+            ),
+            terminal: false,
+            regexes: RegexSet::new(&[
+                r"https://github\.com/(?P<owner>[^/]+)/(?P<repo>[^/]+)",
+            ])?,
+            schemes: Vec::new(),
+        };
+
+        let regex_apps =
+            RegexApps(vec![catch_all.clone(), specific.clone()]);
+
+        let path = UserPath::Url(Url::parse(
+            "https://github.com/sirn-se/handlr-regex",
+        )?);
+
+        let matches = regex_apps.matches_for(&path);
+
+        assert_eq!(matches, vec![&catch_all, &specific]);
+        assert_eq!(matches[0].exec_for(&path), "xdg-open %u");
+        assert_eq!(
+            matches[1].exec_for(&path),
+            "git-clone-tui https://github.com/sirn-se/handlr-regex"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_handler_prefers_scheme_match_over_earlier_regex_handler(
+    ) -> Result<()> {
+        let regex_handler = RegexHandler {
+            exec: String::from("generic-mail-regex-handler %u"),
+            terminal: false,
+            regexes: RegexSet::new(&[r"^mailto:.*"])?,
+            schemes: Vec::new(),
+        };
+        let scheme_handler = RegexHandler {
+            exec: String::from("xdg-email %u"),
+            terminal: false,
+            regexes: RegexSet::new(Vec::<String>::new())?,
+            schemes: vec![String::from("mailto")],
+        };
+
+        // The regex handler is listed first, but the scheme handler must
+        // still win, per the scheme-takes-priority requirement.
+        let regex_apps =
+            RegexApps(vec![regex_handler.clone(), scheme_handler.clone()]);
+
+        let path = UserPath::Url(Url::parse("mailto:user@example.com")?);
+
+        let (handler, _captures) = regex_apps.get_handler(&path)?;
+        assert_eq!(handler, scheme_handler);
+
+        assert_eq!(
+            regex_apps.matches_for(&path),
+            vec![&scheme_handler, &regex_handler]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn expand_captures_handles_edge_cases() {
+        let mut captures = HashMap::new();
+        captures.insert("owner".to_string(), "foo".to_string());
+
+        assert_eq!(expand_captures("$owner", &captures), "foo");
+        assert_eq!(expand_captures("${owner}", &captures), "foo");
+        assert_eq!(expand_captures("$$owner", &captures), "$owner");
+        assert_eq!(expand_captures("$missing", &captures), "");
+    }
+
+    #[test]
+    fn expand_captures_escapes_percent_in_substituted_values() {
+        let mut captures = HashMap::new();
+        captures.insert("path".to_string(), "a%20b".to_string());
+        captures.insert("field_code".to_string(), "%f".to_string());
+
+        assert_eq!(
+            expand_captures("open $path", &captures),
+            "open a%%20b",
+            "a literal % in a capture must be escaped so it can't be \
+             misread as a field code by the Exec= machinery"
+        );
+        assert_eq!(
+            expand_captures("open ${field_code}", &captures),
+            "open %%f",
+        );
+    }
+
+    #[test]
+    fn regex_handler_round_trips_through_config() -> Result<()> {
+        let regex_handler = RegexHandler {
+            exec: String::from("freetube %u"),
+            terminal: false,
+            regexes: RegexSet::new(&[
+                r"(https://)?(www\.)?youtu(be\.com|\.be)/*",
+            ])?,
+            schemes: Vec::new(),
+        };
+        let regex_apps = RegexApps(vec![regex_handler]);
+
+        let serialized =
+            toml::to_string(&regex_apps).expect("failed to serialize");
+        let deserialized: RegexApps =
+            toml::from_str(&serialized).expect("failed to deserialize");
+
+        assert_eq!(regex_apps, deserialized);
+
+        Ok(())
+    }
+
+    #[test]
+    fn regex_handler_round_trips_flags() -> Result<()> {
+        let regex_handler = RegexHandler {
+            exec: String::from("freetube %u"),
+            terminal: false,
+            regexes: RegexSet::new_with_flags(
+                &[r"^HTTPS://YOUTU\.BE/"],
+                RegexFlags {
+                    case_insensitive: true,
+                    anchored: false,
+                    multi_line: true,
+                    dot_matches_new_line: true,
+                },
+            )?,
+            schemes: Vec::new(),
+        };
+        let regex_apps = RegexApps(vec![regex_handler]);
+
+        let serialized =
+            toml::to_string(&regex_apps).expect("failed to serialize");
+        let deserialized: RegexApps =
+            toml::from_str(&serialized).expect("failed to deserialize");
+
+        assert_eq!(regex_apps, deserialized);
+        assert!(
+            deserialized.0[0].regexes.flags.case_insensitive,
+            "case_insensitive flag must survive a serialize/deserialize round-trip"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn anchored_regex_handler_round_trips_without_rewrapping(
+    ) -> Result<()> {
+        let regex_handler = RegexHandler {
+            exec: String::from("freetube %u"),
+            terminal: false,
+            regexes: RegexSet::new_with_flags(
+                &[r"HTTPS://YOUTU\.BE/"],
+                RegexFlags {
+                    anchored: true,
+                    ..RegexFlags::default()
+                },
+            )?,
+            schemes: Vec::new(),
+        };
+        let regex_apps = RegexApps(vec![regex_handler]);
+
+        let serialized =
+            toml::to_string(&regex_apps).expect("failed to serialize");
+        let deserialized: RegexApps =
+            toml::from_str(&serialized).expect("failed to deserialize");
+
+        assert_eq!(
+            regex_apps, deserialized,
+            "anchored handlers must round-trip without the stored pattern \
+             growing an extra wrapping layer each cycle"
+        );
+        assert_eq!(
+            deserialized.0[0].regexes.patterns,
+            vec![r"HTTPS://YOUTU\.BE/".to_string()],
+            "the persisted pattern must stay unwrapped"
+        );
+
+        // Serializing a second time must be stable, not grow further
+        let reserialized = toml::to_string(&deserialized)
+            .expect("failed to serialize a second time");
+        assert_eq!(serialized, reserialized);
+
+        Ok(())
+    }
 }